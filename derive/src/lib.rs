@@ -0,0 +1,254 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! `#[derive(RData)]`, generates the `read`/`emit`/`parse` boilerplate shared
+//! by every RData module
+//!
+//! Every RData module (MX, CAA, ...) walks its fields in wire order to
+//! decode, walks them again in the same order to encode, and walks them a
+//! third time to parse the master-file token form. Those three walks are
+//! almost always the same sequence of "read a u16", "read a Name", "read a
+//! trailing byte blob" operations, hand-written slightly differently in
+//! every module. This crate generates them from the field list and a small
+//! `#[dns(..)]` attribute per field, so new RData modules only need to state
+//! their wire format once.
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use syn::{Data, DeriveInput, Fields, Ident};
+
+/// The wire representation of a single field, as stated by its `#[dns(..)]` attribute
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    U8,
+    U16,
+    U32,
+    /// A `Name`, read and emitted as-is
+    Name,
+    /// A `Name` whose emission respects `encoder.is_canonical_names()`, lowercasing
+    /// per [RFC 4034, 6.2](https://tools.ietf.org/html/rfc4034#section-6.2)
+    CanonicalName,
+    /// A length-prefixed `CharacterString`
+    CharacterString,
+    /// The remaining RDATA octets, read and emitted verbatim
+    Bytes,
+}
+
+fn field_kind(field: &syn::Field) -> FieldKind {
+    for attr in &field.attrs {
+        if !attr.path.is_ident("dns") {
+            continue;
+        }
+
+        let meta = attr.parse_meta().expect("invalid #[dns(..)] attribute");
+        if let syn::Meta::List(ref list) = meta {
+            for nested in &list.nested {
+                if let syn::NestedMeta::Meta(syn::Meta::Path(ref path)) = nested {
+                    if path.is_ident("u8") {
+                        return FieldKind::U8;
+                    } else if path.is_ident("u16") {
+                        return FieldKind::U16;
+                    } else if path.is_ident("u32") {
+                        return FieldKind::U32;
+                    } else if path.is_ident("name") {
+                        return FieldKind::Name;
+                    } else if path.is_ident("canonical_name") {
+                        return FieldKind::CanonicalName;
+                    } else if path.is_ident("character_string") {
+                        return FieldKind::CharacterString;
+                    } else if path.is_ident("bytes") {
+                        return FieldKind::Bytes;
+                    }
+                }
+            }
+        }
+    }
+
+    panic!("every #[derive(RData)] field needs a #[dns(..)] attribute, e.g. #[dns(u16)]");
+}
+
+/// Derives `read`, `emit`, and `parse` free functions for an RData struct
+///
+/// ```ignore
+/// #[derive(RData)]
+/// struct MX {
+///     #[dns(u16)]
+///     preference: u16,
+///     #[dns(canonical_name)]
+///     exchange: Name,
+/// }
+/// ```
+///
+/// expands to module-level `pub fn read(decoder: &mut BinDecoder) ->
+/// DecodeResult<MX>`, `pub fn emit(encoder: &mut BinEncoder, mx: &MX) ->
+/// EncodeResult`, and `pub fn parse(tokens: &Vec<Token>, origin: Option<&Name>)
+/// -> ParseResult<MX>` — the same signatures every hand-written RData module
+/// already exposes, so callers (and the zone parser) don't need to know
+/// whether a given module is derived or hand-written.
+///
+/// If any field is `#[dns(bytes)]` (a trailing blob that runs to the end of
+/// the RDATA), the generated `read` also takes an `rdata_length: u16`
+/// parameter, matching the pattern used by `CAA` and `Unknown`.
+#[proc_macro_derive(RData, attributes(dns))]
+pub fn derive_rdata(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse(input).expect("failed to parse #[derive(RData)] input");
+    let struct_name = &input.ident;
+
+    let fields = match input.data {
+        Data::Struct(ref data) => {
+            match data.fields {
+                Fields::Named(ref fields) => &fields.named,
+                _ => panic!("#[derive(RData)] only supports structs with named fields"),
+            }
+        }
+        _ => panic!("#[derive(RData)] only supports structs"),
+    };
+
+    let field_info: Vec<(&Ident, FieldKind)> =
+        fields.iter().map(|f| (f.ident.as_ref().unwrap(), field_kind(f))).collect();
+
+    let has_trailing_bytes = field_info.iter().any(|&(_, kind)| kind == FieldKind::Bytes);
+
+    let read_stmts = field_info.iter().map(|&(ident, kind)| {
+        match kind {
+            FieldKind::U8 => quote! { let #ident = decoder.read_u8()?; },
+            FieldKind::U16 => quote! { let #ident = decoder.read_u16()?; },
+            FieldKind::U32 => quote! { let #ident = decoder.read_u32()?; },
+            FieldKind::Name | FieldKind::CanonicalName => {
+                quote! { let #ident = Name::read(decoder)?; }
+            }
+            FieldKind::CharacterString => {
+                quote! { let #ident = CharacterString::read(decoder)?; }
+            }
+            FieldKind::Bytes => {
+                quote! {
+                    let read_so_far = decoder.index() - start_index;
+                    if read_so_far > rdata_length as usize {
+                        return Err(DecodeErrorKind::Msg(
+                            format!("read {} bytes, which exceeds RDATA length {}",
+                                    read_so_far, rdata_length)).into());
+                    }
+                    let #ident = decoder.read_vec(rdata_length as usize - read_so_far)?;
+                }
+            }
+        }
+    });
+
+    let field_idents = field_info.iter().map(|&(ident, _)| ident);
+    let read_signature = if has_trailing_bytes {
+        quote! { pub fn read(decoder: &mut BinDecoder, rdata_length: u16) -> DecodeResult<#struct_name> }
+    } else {
+        quote! { pub fn read(decoder: &mut BinDecoder) -> DecodeResult<#struct_name> }
+    };
+    let start_index_stmt = if has_trailing_bytes {
+        quote! { let start_index = decoder.index(); }
+    } else {
+        quote! {}
+    };
+
+    let emit_stmts = field_info.iter().map(|&(ident, kind)| {
+        match kind {
+            FieldKind::U8 => quote! { encoder.emit_u8(this.#ident)?; },
+            FieldKind::U16 => quote! { encoder.emit_u16(this.#ident)?; },
+            FieldKind::U32 => quote! { encoder.emit_u32(this.#ident)?; },
+            FieldKind::Name => quote! { this.#ident.emit(encoder)?; },
+            FieldKind::CanonicalName => {
+                quote! {
+                    this.#ident.emit_with_lowercase(encoder, encoder.is_canonical_names())?;
+                }
+            }
+            FieldKind::CharacterString => quote! { this.#ident.emit(encoder)?; },
+            FieldKind::Bytes => quote! { encoder.emit_vec(&this.#ident)?; },
+        }
+    });
+
+    let parse_stmts = field_info.iter().map(|&(ident, kind)| {
+        let field_name = ident.to_string();
+        match kind {
+            FieldKind::U8 | FieldKind::U16 | FieldKind::U32 => {
+                quote! {
+                    let #ident = token.next()
+                        .ok_or(ParseError::from(ParseErrorKind::MissingToken(#field_name.to_string())))
+                        .and_then(|t| if let &Token::CharData(ref s) = t {
+                            Ok(s.parse()?)
+                        } else {
+                            Err(ParseErrorKind::UnexpectedToken(t.clone()).into())
+                        })?;
+                }
+            }
+            FieldKind::Name | FieldKind::CanonicalName => {
+                quote! {
+                    let #ident = token.next()
+                        .ok_or(ParseError::from(ParseErrorKind::MissingToken(#field_name.to_string())))
+                        .and_then(|t| if let &Token::CharData(ref s) = t {
+                            Name::parse(s, origin)
+                        } else {
+                            Err(ParseErrorKind::UnexpectedToken(t.clone()).into())
+                        })?;
+                }
+            }
+            FieldKind::CharacterString => {
+                quote! {
+                    let #ident = token.next()
+                        .ok_or(ParseError::from(ParseErrorKind::MissingToken(#field_name.to_string())))
+                        .and_then(|t| if let &Token::CharData(ref s) = t {
+                            Ok(CharacterString::new(s.clone()))
+                        } else {
+                            Err(ParseErrorKind::UnexpectedToken(t.clone()).into())
+                        })?;
+                }
+            }
+            FieldKind::Bytes => {
+                quote! {
+                    let #ident = token.next()
+                        .ok_or(ParseError::from(ParseErrorKind::MissingToken(#field_name.to_string())))
+                        .and_then(|t| if let &Token::CharData(ref s) = t {
+                            Ok(s.clone().into_bytes())
+                        } else {
+                            Err(ParseErrorKind::UnexpectedToken(t.clone()).into())
+                        })?;
+                }
+            }
+        }
+    });
+    let parse_field_idents = field_info.iter().map(|&(ident, _)| ident);
+
+    let expanded = quote! {
+        #read_signature {
+            #start_index_stmt
+            #(#read_stmts)*
+            Ok(#struct_name::new(#(#field_idents),*))
+        }
+
+        pub fn emit(encoder: &mut BinEncoder, this: &#struct_name) -> EncodeResult {
+            #(#emit_stmts)*
+            Ok(())
+        }
+
+        pub fn parse(tokens: &Vec<Token>, origin: Option<&Name>) -> ParseResult<#struct_name> {
+            let mut token = tokens.iter();
+            #(#parse_stmts)*
+            Ok(#struct_name::new(#(#parse_field_idents),*))
+        }
+    };
+
+    expanded.into()
+}
@@ -0,0 +1,248 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! the DNS CLASS of a record, query, or zone
+
+use std::fmt;
+use std::str::FromStr;
+
+use ::serialize::binary::*;
+use ::error::*;
+
+/// [RFC 1035, DOMAIN NAMES - IMPLEMENTATION AND SPECIFICATION, November 1987](https://tools.ietf.org/html/rfc1035)
+///
+/// ```text
+/// 3.2.4. CLASS values
+///
+/// CLASS fields appear in resource records.  The following CLASS mnemonics
+/// and values are defined:
+///
+///     IN              1 the Internet
+///     CS              2 the CSNET class (Obsolete - used only for examples in
+///                     some obsolete RFCs)
+///     CH              3 the CHAOS class
+///     HS              4 Hesiod [Dyer 87]
+///
+/// 3.2.5. QCLASS values
+///
+/// QCLASS fields appear in the question section of a query.  QCLASS values
+/// are a superset of CLASS values; every CLASS is a valid QCLASS.  In
+/// addition to CLASS values, the following QCLASSes are defined:
+///
+///     *               255 any class
+/// ```
+///
+/// Unlike [`RecordType`](super::record_type::RecordType), `Class` is not an
+/// enum: the wire format allows any 16-bit value, and a resolver or zone
+/// parser that only understands the mnemonic forms would lose data on a CH
+/// or HS zone, or on an unrecognized class it's merely forwarding. `Class`
+/// always preserves the raw value; [`KnownClass`] is provided for matching
+/// against the handful of classes this crate gives special meaning to.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Class(u16);
+
+impl Class {
+    /// The Internet
+    pub const IN: Class = Class(1);
+
+    /// The CHAOS class
+    pub const CH: Class = Class(3);
+
+    /// Hesiod
+    pub const HS: Class = Class(4);
+
+    /// QCLASS: none of the listed classes, used in dynamic update prerequisites
+    /// to require a name or rrset to not exist
+    pub const NONE: Class = Class(254);
+
+    /// QCLASS: any class, used in queries and dynamic update deletions
+    pub const ANY: Class = Class(255);
+
+    /// Constructs a `Class` from its raw wire value, known or not
+    pub fn new(value: u16) -> Self {
+        Class(value)
+    }
+
+    /// The raw 16-bit wire value of this class
+    pub fn value(self) -> u16 {
+        self.0
+    }
+
+    /// `true` if this is one of the classes `KnownClass` can name
+    pub fn is_known(self) -> bool {
+        KnownClass::from_class(self).is_some()
+    }
+
+    /// The `KnownClass` this value matches, if any
+    pub fn to_known(self) -> Option<KnownClass> {
+        KnownClass::from_class(self)
+    }
+}
+
+/// The subset of [`Class`] values this crate gives special meaning to, for use in `match`
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum KnownClass {
+    /// The Internet
+    IN,
+    /// The CHAOS class
+    CH,
+    /// Hesiod
+    HS,
+    /// QCLASS: none of the listed classes
+    NONE,
+    /// QCLASS: any class
+    ANY,
+}
+
+impl KnownClass {
+    fn from_class(class: Class) -> Option<KnownClass> {
+        match class {
+            Class::IN => Some(KnownClass::IN),
+            Class::CH => Some(KnownClass::CH),
+            Class::HS => Some(KnownClass::HS),
+            Class::NONE => Some(KnownClass::NONE),
+            Class::ANY => Some(KnownClass::ANY),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            KnownClass::IN => "IN",
+            KnownClass::CH => "CH",
+            KnownClass::HS => "HS",
+            KnownClass::NONE => "NONE",
+            KnownClass::ANY => "ANY",
+        }
+    }
+}
+
+impl From<KnownClass> for Class {
+    fn from(known: KnownClass) -> Self {
+        match known {
+            KnownClass::IN => Class::IN,
+            KnownClass::CH => Class::CH,
+            KnownClass::HS => Class::HS,
+            KnownClass::NONE => Class::NONE,
+            KnownClass::ANY => Class::ANY,
+        }
+    }
+}
+
+impl From<u16> for Class {
+    fn from(value: u16) -> Self {
+        Class(value)
+    }
+}
+
+impl From<Class> for u16 {
+    fn from(class: Class) -> Self {
+        class.0
+    }
+}
+
+/// Read the Class from the given Decoder
+pub fn read(decoder: &mut BinDecoder) -> DecodeResult<Class> {
+    Ok(Class(try!(decoder.read_u16())))
+}
+
+/// Emit the Class to the given Encoder
+pub fn emit(encoder: &mut BinEncoder, class: Class) -> EncodeResult {
+    try!(encoder.emit_u16(class.value()));
+    Ok(())
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self.to_known() {
+            Some(known) => f.write_str(known.as_str()),
+            None => write!(f, "CLASS{}", self.0),
+        }
+    }
+}
+
+impl FromStr for Class {
+    type Err = ParseError;
+
+    /// Accepts the mnemonic forms (`IN`, `CH`, `HS`, `NONE`, `ANY`, matched
+    /// case-insensitively) as well as the RFC 3597 generic `CLASS<n>` syntax
+    /// for any other value.
+    fn from_str(s: &str) -> ParseResult<Class> {
+        match s.to_uppercase().as_str() {
+            "IN" => Ok(Class::IN),
+            "CH" => Ok(Class::CH),
+            "HS" => Ok(Class::HS),
+            "NONE" => Ok(Class::NONE),
+            "ANY" | "*" => Ok(Class::ANY),
+            s if s.starts_with("CLASS") => {
+                s[5..]
+                    .parse()
+                    .map(Class)
+                    .map_err(|_| ParseErrorKind::Msg(format!("invalid class: {}", s)).into())
+            }
+            s => Err(ParseErrorKind::Msg(format!("invalid class: {}", s)).into()),
+        }
+    }
+}
+
+#[test]
+pub fn test() {
+    let class = Class::IN;
+
+    let mut bytes = Vec::new();
+    let mut encoder: BinEncoder = BinEncoder::new(&mut bytes);
+    assert!(emit(&mut encoder, class).is_ok());
+    let bytes = encoder.as_bytes();
+
+    println!("bytes: {:?}", bytes);
+
+    let mut decoder: BinDecoder = BinDecoder::new(bytes);
+    let read_class = read(&mut decoder);
+    assert!(read_class.is_ok(),
+            format!("error decoding: {:?}", read_class.unwrap_err()));
+    assert_eq!(class, read_class.unwrap());
+}
+
+#[test]
+pub fn test_unknown_round_trips() {
+    let class = Class::new(1234);
+    assert!(!class.is_known());
+
+    let mut bytes = Vec::new();
+    let mut encoder: BinEncoder = BinEncoder::new(&mut bytes);
+    assert!(emit(&mut encoder, class).is_ok());
+    let bytes = encoder.as_bytes();
+
+    let mut decoder: BinDecoder = BinDecoder::new(bytes);
+    let read_class = read(&mut decoder);
+    assert!(read_class.is_ok());
+    assert_eq!(class, read_class.unwrap());
+}
+
+#[test]
+pub fn test_display_and_from_str() {
+    assert_eq!(Class::IN.to_string(), "IN");
+    assert_eq!(Class::CH.to_string(), "CH");
+    assert_eq!(Class::HS.to_string(), "HS");
+    assert_eq!(Class::NONE.to_string(), "NONE");
+    assert_eq!(Class::ANY.to_string(), "ANY");
+    assert_eq!(Class::new(1234).to_string(), "CLASS1234");
+
+    assert_eq!("IN".parse::<Class>().unwrap(), Class::IN);
+    assert_eq!("ch".parse::<Class>().unwrap(), Class::CH);
+    assert_eq!("CLASS1234".parse::<Class>().unwrap(), Class::new(1234));
+    assert!("bogus".parse::<Class>().is_err());
+}
@@ -0,0 +1,526 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! certification authority authorization record
+
+use std::fmt;
+use std::str;
+
+use ::serialize::txt::*;
+use ::serialize::binary::*;
+use ::error::*;
+use rr::domain::Name;
+
+/// [RFC 8659, DNS Certification Authority Authorization (CAA) Resource Record, November 2019](https://tools.ietf.org/html/rfc8659)
+///
+/// ```text
+/// 4.  The CAA RR Type
+///
+///    The Certification Authority Authorization (CAA) DNS Resource
+///    Record allows a DNS domain name holder to specify one or more
+///    Certification Authorities (CAs) authorized to issue certificates
+///    for that domain.
+///
+/// 4.1.  Syntax
+///
+///    A CAA RR contains a single property entry consisting of a tag-value
+///    pair.  Each tag represents an assertion by the domain about
+///    certificate issuance, and the value of the tag is data that can be
+///    used to implement that assertion.
+///
+///    +0-1-2-3-4-5-6-7-+
+///    |   Flags         |
+///    +----------------+
+///    |  Tag Length = n |
+///    +----------------+...+---------------+
+///    |  Tag char 1     |...|  Tag char n   |
+///    +----------------+...+---------------+
+///    +----------------+...+---------------+...+---------------+
+///    |  Value byte 1   |...|  Value byte m |
+///    +----------------+...+---------------+
+///
+/// 4.2.  Critical Flag
+///
+///    The Flags octet is interpreted as a bit map, and has only one bit
+///    defined:  the Issuer Critical Flag (bit 0, the most significant
+///    bit, value 128).
+/// ```
+const ISSUER_CRITICAL: u8 = 0b1000_0000;
+
+/// The "property" of a CAA record, the left-hand side of the tag-value pair
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Property {
+    /// [RFC 8659, 4.3](https://tools.ietf.org/html/rfc8659#section-4.3)
+    ///
+    /// The issue property entry authorizes the holder of the domain name
+    /// Issuer Domain Name or a party acting under the explicit authority of
+    /// the holder of that domain name to issue certificates for the domain
+    /// in which the property is published.
+    Issue,
+
+    /// [RFC 8659, 4.3](https://tools.ietf.org/html/rfc8659#section-4.3)
+    ///
+    /// The issuewild property entry authorizes the holder of the domain
+    /// name Issuer Domain Name or a party acting under the explicit
+    /// authority of the holder of that domain name to issue wildcard
+    /// certificates for the domain in which the property is published.
+    IssueWild,
+
+    /// [RFC 8659, 4.4](https://tools.ietf.org/html/rfc8659#section-4.4)
+    ///
+    /// The iodef property specifies a URL to which an issuer MAY report
+    /// certificate-issue requests that are inconsistent with the issuer's
+    /// Certification Practice.
+    Iodef,
+
+    /// An unrecognized property tag, preserved verbatim
+    Unknown(String),
+}
+
+impl Property {
+    fn as_str(&self) -> &str {
+        match *self {
+            Property::Issue => "issue",
+            Property::IssueWild => "issuewild",
+            Property::Iodef => "iodef",
+            Property::Unknown(ref tag) => tag,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Property {
+    fn from(tag: &'a str) -> Property {
+        match tag {
+            "issue" => Property::Issue,
+            "issuewild" => Property::IssueWild,
+            "iodef" => Property::Iodef,
+            _ => Property::Unknown(tag.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A single `tag=value` parameter as found in the value of an `issue`/`issuewild` property
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct KeyValue {
+    key: String,
+    value: String,
+}
+
+impl KeyValue {
+    /// Constructs a new KeyValue pair
+    pub fn new<K: Into<String>, V: Into<String>>(key: K, value: V) -> KeyValue {
+        KeyValue {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+
+    /// The key (left hand side) of the parameter
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The value (right hand side) of the parameter
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+impl fmt::Display for KeyValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{}={}", self.key, self.value)
+    }
+}
+
+/// The value associated with a CAA property, parsed according to its tag
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Value {
+    /// Value of the `issue`/`issuewild` properties
+    ///
+    /// `None` as the issuer domain name means "no CA is authorized", written
+    /// in the presentation format as the single character `;`.
+    Issuer(Option<Name>, Vec<KeyValue>),
+
+    /// Value of the `iodef` property, a URL
+    Url(String),
+
+    /// Value of a tag this crate does not otherwise recognize, the raw bytes
+    Unknown(Vec<u8>),
+}
+
+/// [RFC 8659, DNS Certification Authority Authorization (CAA) Resource Record, November 2019](https://tools.ietf.org/html/rfc8659)
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct CAA {
+    issuer_critical: bool,
+    tag: Property,
+    value: Value,
+}
+
+impl CAA {
+    /// Constructs a new CAA RData
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer_critical` - the issuer critical flag
+    /// * `tag` - the property tag
+    /// * `value` - the parsed value for the property
+    ///
+    /// # Returns
+    ///
+    /// A new CAA RData for use in a Resource Record
+    pub fn new(issuer_critical: bool, tag: Property, value: Value) -> CAA {
+        CAA {
+            issuer_critical: issuer_critical,
+            tag: tag,
+            value: value,
+        }
+    }
+
+    /// [RFC 8659, 4.2](https://tools.ietf.org/html/rfc8659#section-4.2)
+    ///
+    /// ```text
+    ///    If the Issuer Critical Flag is set (one), the property is
+    ///    considered critical, and an issuer MUST NOT issue certificates for
+    ///    a domain that contains a CAA critical property for an unknown or
+    ///    unsupported property tag.
+    /// ```
+    pub fn issuer_critical(&self) -> bool {
+        self.issuer_critical
+    }
+
+    /// The property (tag) this record asserts
+    pub fn tag(&self) -> &Property {
+        &self.tag
+    }
+
+    /// The parsed value associated with the tag
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+}
+
+/// A property tag is a sequence of 1-15 lowercase US-ASCII letters/digits,
+/// per [RFC 8659, 4.1](https://tools.ietf.org/html/rfc8659#section-4.1)
+fn valid_tag(tag: &str) -> bool {
+    !tag.is_empty() && tag.len() <= 15 &&
+    tag.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+}
+
+fn parse_value(tag: &Property, bytes: &[u8]) -> DecodeResult<Value> {
+    match *tag {
+        Property::Issue | Property::IssueWild => {
+            let value = try!(str::from_utf8(bytes)
+                .map_err(|_| DecodeErrorKind::Msg("CAA value is not valid UTF-8".to_string())));
+
+            if value == ";" {
+                return Ok(Value::Issuer(None, Vec::new()));
+            }
+
+            let mut parts = value.split(';');
+            let issuer_part = parts.next().unwrap_or("").trim();
+            let issuer = if issuer_part.is_empty() {
+                None
+            } else {
+                Some(try!(Name::parse(issuer_part, None)
+                    .map_err(|_| DecodeErrorKind::Msg("invalid CAA issuer domain name".to_string()))))
+            };
+
+            let mut key_values = Vec::new();
+            for param in parts {
+                let param = param.trim();
+                if param.is_empty() {
+                    continue;
+                }
+
+                let mut kv = param.splitn(2, '=');
+                let key = kv.next().unwrap_or("").trim();
+                let val = kv.next().unwrap_or("").trim();
+                key_values.push(KeyValue::new(key, val));
+            }
+
+            Ok(Value::Issuer(issuer, key_values))
+        }
+        Property::Iodef => {
+            let url = try!(str::from_utf8(bytes)
+                .map_err(|_| DecodeErrorKind::Msg("CAA value is not valid UTF-8".to_string())));
+            Ok(Value::Url(url.to_string()))
+        }
+        Property::Unknown(_) => Ok(Value::Unknown(bytes.to_vec())),
+    }
+}
+
+fn emit_value(value: &Value) -> Vec<u8> {
+    match *value {
+        Value::Issuer(ref issuer, ref key_values) => {
+            let mut s = match *issuer {
+                Some(ref name) => name.to_string(),
+                None => String::new(),
+            };
+
+            for kv in key_values {
+                s.push(';');
+                s.push_str(&kv.to_string());
+            }
+
+            if issuer.is_none() && key_values.is_empty() {
+                s.push(';');
+            }
+
+            s.into_bytes()
+        }
+        Value::Url(ref url) => url.clone().into_bytes(),
+        Value::Unknown(ref bytes) => bytes.clone(),
+    }
+}
+
+/// Read the RData from the given Decoder
+pub fn read(decoder: &mut BinDecoder, rdata_length: u16) -> DecodeResult<CAA> {
+    let start_index = decoder.index();
+
+    let flags: u8 = try!(decoder.read_u8());
+    let issuer_critical = flags & ISSUER_CRITICAL == ISSUER_CRITICAL;
+
+    let tag_length: u8 = try!(decoder.read_u8());
+
+    // 1 octet of flags + 1 octet of tag length must already be accounted for
+    // in `rdata_length`; make sure the declared tag length doesn't run past
+    // the RDATA this record is allowed to occupy before we read it.
+    if 2 + tag_length as usize > rdata_length as usize {
+        return Err(DecodeErrorKind::Msg(format!("CAA tag length {} exceeds RDATA length {}",
+                                                  tag_length,
+                                                  rdata_length))
+            .into());
+    }
+
+    let tag_bytes = try!(decoder.read_vec(tag_length as usize));
+    let tag_str = try!(str::from_utf8(&tag_bytes)
+        .map_err(|_| DecodeErrorKind::Msg("CAA tag is not valid US-ASCII".to_string())));
+
+    if !valid_tag(tag_str) {
+        return Err(DecodeErrorKind::Msg("invalid CAA tag".to_string()).into());
+    }
+
+    let tag = Property::from(tag_str);
+
+    let read_so_far = decoder.index() - start_index;
+    if read_so_far > rdata_length as usize {
+        return Err(DecodeErrorKind::Msg(format!("read {} bytes, which exceeds RDATA length {}",
+                                                  read_so_far,
+                                                  rdata_length))
+            .into());
+    }
+    let value_length = rdata_length as usize - read_so_far;
+    let value_bytes = try!(decoder.read_vec(value_length));
+    let value = try!(parse_value(&tag, &value_bytes));
+
+    Ok(CAA::new(issuer_critical, tag, value))
+}
+
+/// Emit the RData to the given Encoder
+///
+/// `emit` writes the value re-encoded from its parsed form: an `issue`/
+/// `issuewild` value is the issuer name followed by each `key=value`
+/// parameter, joined by `;` with no whitespace, so whitespace in the value as
+/// read is not reproduced; `iodef` and unrecognized values are written as
+/// read. Unlike Names, CAA values are never canonicalized or lowercased.
+pub fn emit(encoder: &mut BinEncoder, caa: &CAA) -> EncodeResult {
+    let flags = if caa.issuer_critical() { ISSUER_CRITICAL } else { 0 };
+    try!(encoder.emit_u8(flags));
+
+    let tag = caa.tag().as_str();
+    try!(encoder.emit_u8(tag.len() as u8));
+    try!(encoder.emit_vec(tag.as_bytes()));
+
+    let value = emit_value(caa.value());
+    try!(encoder.emit_vec(&value));
+    Ok(())
+}
+
+/// Parse the RData from a set of Tokens
+pub fn parse(tokens: &Vec<Token>, _origin: Option<&Name>) -> ParseResult<CAA> {
+    let mut token = tokens.iter();
+
+    let flags: u8 = try!(token.next()
+        .ok_or(ParseError::from(ParseErrorKind::MissingToken("flags".to_string())))
+        .and_then(|t| if let &Token::CharData(ref s) = t {
+            Ok(try!(s.parse()))
+        } else {
+            Err(ParseErrorKind::UnexpectedToken(t.clone()).into())
+        }));
+    let issuer_critical = flags & ISSUER_CRITICAL == ISSUER_CRITICAL;
+
+    let tag_str: String = try!(token.next()
+        .ok_or(ParseError::from(ParseErrorKind::MissingToken("tag".to_string())))
+        .and_then(|t| if let &Token::CharData(ref s) = t {
+            Ok(s.clone())
+        } else {
+            Err(ParseErrorKind::UnexpectedToken(t.clone()).into())
+        }));
+
+    if !valid_tag(&tag_str) {
+        return Err(ParseErrorKind::Msg("invalid CAA tag".to_string()).into());
+    }
+
+    let tag = Property::from(tag_str.as_str());
+
+    // the value is the remaining quoted-string token in the master file
+    let value_str: String = try!(token.next()
+        .ok_or(ParseError::from(ParseErrorKind::MissingToken("value".to_string())))
+        .and_then(|t| if let &Token::CharData(ref s) = t {
+            Ok(s.clone())
+        } else {
+            Err(ParseErrorKind::UnexpectedToken(t.clone()).into())
+        }));
+
+    let value = try!(parse_value(&tag, value_str.as_bytes())
+        .map_err(|e| ParseErrorKind::Msg(format!("invalid CAA value: {:?}", e))));
+
+    Ok(CAA::new(issuer_critical, tag, value))
+}
+
+impl fmt::Display for CAA {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let flags = if self.issuer_critical { ISSUER_CRITICAL } else { 0 };
+        let value = emit_value(&self.value);
+        let value_str = String::from_utf8_lossy(&value);
+        write!(f, "{flags} {tag} \"{value}\"", flags = flags, tag = self.tag, value = value_str)
+    }
+}
+
+#[test]
+pub fn test() {
+    let rdata = CAA::new(false,
+                          Property::Issue,
+                          Value::Issuer(Some(Name::new().label("letsencrypt").label("org")),
+                                         Vec::new()));
+
+    let mut bytes = Vec::new();
+    let mut encoder: BinEncoder = BinEncoder::new(&mut bytes);
+    assert!(emit(&mut encoder, &rdata).is_ok());
+    let bytes = encoder.as_bytes();
+
+    println!("bytes: {:?}", bytes);
+
+    let mut decoder: BinDecoder = BinDecoder::new(bytes);
+    let read_rdata = read(&mut decoder, bytes.len() as u16);
+    assert!(read_rdata.is_ok(),
+            format!("error decoding: {:?}", read_rdata.unwrap_err()));
+    assert_eq!(rdata, read_rdata.unwrap());
+}
+
+#[test]
+pub fn test_no_issuer() {
+    let rdata = CAA::new(true, Property::Issue, Value::Issuer(None, Vec::new()));
+
+    let mut bytes = Vec::new();
+    let mut encoder: BinEncoder = BinEncoder::new(&mut bytes);
+    assert!(emit(&mut encoder, &rdata).is_ok());
+    let bytes = encoder.as_bytes();
+
+    let mut decoder: BinDecoder = BinDecoder::new(bytes);
+    let read_rdata = read(&mut decoder, bytes.len() as u16);
+    assert!(read_rdata.is_ok());
+    assert_eq!(rdata, read_rdata.unwrap());
+}
+
+#[test]
+pub fn test_iodef() {
+    let rdata = CAA::new(false,
+                          Property::Iodef,
+                          Value::Url("mailto:security@example.com".to_string()));
+
+    let mut bytes = Vec::new();
+    let mut encoder: BinEncoder = BinEncoder::new(&mut bytes);
+    assert!(emit(&mut encoder, &rdata).is_ok());
+    let bytes = encoder.as_bytes();
+
+    let mut decoder: BinDecoder = BinDecoder::new(bytes);
+    let read_rdata = read(&mut decoder, bytes.len() as u16);
+    assert!(read_rdata.is_ok());
+    assert_eq!(rdata, read_rdata.unwrap());
+}
+
+#[test]
+pub fn test_issuer_with_key_values() {
+    let rdata = CAA::new(false,
+                          Property::Issue,
+                          Value::Issuer(Some(Name::new().label("letsencrypt").label("org")),
+                                         vec![KeyValue::new("validationmethods", "dns-01"),
+                                              KeyValue::new("accounturi",
+                                                             "https://example.com/acct/1")]));
+
+    let mut bytes = Vec::new();
+    let mut encoder: BinEncoder = BinEncoder::new(&mut bytes);
+    assert!(emit(&mut encoder, &rdata).is_ok());
+    let bytes = encoder.as_bytes();
+
+    let mut decoder: BinDecoder = BinDecoder::new(bytes);
+    let read_rdata = read(&mut decoder, bytes.len() as u16);
+    assert!(read_rdata.is_ok());
+    assert_eq!(rdata, read_rdata.unwrap());
+}
+
+#[test]
+pub fn test_parse_issue() {
+    let tokens = vec![Token::CharData("0".to_string()),
+                       Token::CharData("issue".to_string()),
+                       Token::CharData("letsencrypt.org; validationmethods=dns-01".to_string())];
+
+    let rdata = parse(&tokens, None).unwrap();
+    assert_eq!(rdata.issuer_critical(), false);
+    assert_eq!(*rdata.tag(), Property::Issue);
+    assert_eq!(*rdata.value(),
+               Value::Issuer(Some(Name::new().label("letsencrypt").label("org")),
+                              vec![KeyValue::new("validationmethods", "dns-01")]));
+}
+
+#[test]
+pub fn test_parse_issue_no_issuer() {
+    let tokens = vec![Token::CharData("128".to_string()),
+                       Token::CharData("issue".to_string()),
+                       Token::CharData(";".to_string())];
+
+    let rdata = parse(&tokens, None).unwrap();
+    assert_eq!(rdata.issuer_critical(), true);
+    assert_eq!(*rdata.value(), Value::Issuer(None, Vec::new()));
+}
+
+#[test]
+pub fn test_parse_iodef() {
+    let tokens = vec![Token::CharData("0".to_string()),
+                       Token::CharData("iodef".to_string()),
+                       Token::CharData("mailto:security@example.com".to_string())];
+
+    let rdata = parse(&tokens, None).unwrap();
+    assert_eq!(*rdata.tag(), Property::Iodef);
+    assert_eq!(*rdata.value(),
+               Value::Url("mailto:security@example.com".to_string()));
+}
+
+#[test]
+pub fn test_parse_invalid_tag() {
+    let tokens = vec![Token::CharData("0".to_string()),
+                       Token::CharData("Issue".to_string()),
+                       Token::CharData(";".to_string())];
+
+    assert!(parse(&tokens, None).is_err());
+}
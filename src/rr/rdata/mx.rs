@@ -21,6 +21,7 @@ use std::fmt;
 use ::serialize::txt::*;
 use ::serialize::binary::*;
 use ::error::*;
+use ::trust_dns_derive::RData;
 use rr::domain::Name;
 
 /// [RFC 1035, DOMAIN NAMES - IMPLEMENTATION AND SPECIFICATION, November 1987](https://tools.ietf.org/html/rfc1035)
@@ -40,9 +41,15 @@ use rr::domain::Name;
 /// [RFC-974].
 ///
 /// ```
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+///
+/// The `read`/`emit`/`parse` functions below are generated by
+/// `#[derive(RData)]` from the `#[dns(..)]` attribute on each field, rather
+/// than hand-written; see the `trust-dns-derive` crate.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, RData)]
 pub struct MX {
+    #[dns(u16)]
     preference: u16,
+    #[dns(canonical_name)]
     exchange: Name,
 }
 
@@ -86,55 +93,14 @@ impl MX {
     }
 }
 
-/// Read the RData from the given Decoder
-pub fn read(decoder: &mut BinDecoder) -> DecodeResult<MX> {
-    Ok(MX::new(try!(decoder.read_u16()), try!(Name::read(decoder))))
-}
-
-/// [RFC 4034](https://tools.ietf.org/html/rfc4034#section-6), DNSSEC Resource Records, March 2005
-///
-/// ```text
-/// 6.2.  Canonical RR Form
-///
-///    For the purposes of DNS security, the canonical form of an RR is the
-///    wire format of the RR where:
-///
-///    ...
-///
-///    3.  if the type of the RR is NS, MD, MF, CNAME, SOA, MB, MG, MR, PTR,
-///        HINFO, MINFO, MX, HINFO, RP, AFSDB, RT, SIG, PX, NXT, NAPTR, KX,
-///        SRV, DNAME, A6, RRSIG, or NSEC (rfc6840 removes NSEC), all uppercase
-///        US-ASCII letters in the DNS names contained within the RDATA are replaced
-///        by the corresponding lowercase US-ASCII letters;
-/// ```
-pub fn emit(encoder: &mut BinEncoder, mx: &MX) -> EncodeResult {
-    let is_canonical_names = encoder.is_canonical_names();
-    try!(encoder.emit_u16(mx.preference()));
-    try!(mx.exchange().emit_with_lowercase(encoder, is_canonical_names));
-    Ok(())
-}
-
-/// Parse the RData from a set of Tokens
-pub fn parse(tokens: &Vec<Token>, origin: Option<&Name>) -> ParseResult<MX> {
-    let mut token = tokens.iter();
-
-    let preference: u16 = try!(token.next()
-        .ok_or(ParseError::from(ParseErrorKind::MissingToken("preference".to_string())))
-        .and_then(|t| if let &Token::CharData(ref s) = t {
-            Ok(try!(s.parse()))
-        } else {
-            Err(ParseErrorKind::UnexpectedToken(t.clone()).into())
-        }));
-    let exchange: Name = try!(token.next()
-        .ok_or(ParseErrorKind::MissingToken("exchange".to_string()).into())
-        .and_then(|t| if let &Token::CharData(ref s) = t {
-            Name::parse(s, origin)
-        } else {
-            Err(ParseErrorKind::UnexpectedToken(t.clone()).into())
-        }));
-
-    Ok(MX::new(preference, exchange))
-}
+// `read`, `emit`, and `parse` are generated by `#[derive(RData)]` above, from
+// the `#[dns(..)]` attribute on each field. See `trust-dns-derive`.
+//
+// `emit` honors `encoder.is_canonical_names()` for the `exchange` field, per
+// [RFC 4034, 6.2](https://tools.ietf.org/html/rfc4034#section-6.2): when the
+// type of the RR is among those listed there (which includes MX), all
+// uppercase US-ASCII letters in the DNS names contained within the RDATA are
+// replaced by the corresponding lowercase US-ASCII letters.
 
 impl fmt::Display for MX {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
@@ -0,0 +1,252 @@
+/*
+ * Copyright (C) 2015 Benjamin Fry <benjaminfry@me.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! unrecognized resource record type, generic handling via RFC 3597
+
+use std::fmt;
+use std::str;
+
+use ::serialize::txt::*;
+use ::serialize::binary::*;
+use ::error::*;
+use rr::domain::Name;
+
+/// [RFC 3597, Handling of Unknown DNS Resource Record (RR) Types, September 2003](https://tools.ietf.org/html/rfc3597)
+///
+/// ```text
+/// 3.  DNS Packet Format for Unknown RR Types
+///
+///    Wire format of RRs of unknown type carries exactly the RDATA
+///    bytes specified by the RDLENGTH field, interpreted by the code
+///    that uses this type as an opaque octet string.
+///
+/// 5.  Text Representation of RDATA
+///
+///    If the text representation of an RR is required (for example, in a
+///    zone file), an unknown RR is represented in the wire format, using
+///    the following syntax:
+///
+///       \# <length> <hexdata>
+///
+///    The special token `\#` is used to indicate that the record is
+///    represented in the "generic" encoding.  `<length>` specifies the
+///    length of the RDATA in octets, and is interpreted as a decimal
+///    integer.  `<hexdata>` is a hexadecimal dump of the RDATA, which
+///    may be split into multiple, whitespace-separated substrings.
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Unknown {
+    rr_type: u16,
+    rdata: Vec<u8>,
+}
+
+impl Unknown {
+    /// Constructs a new Unknown RData
+    ///
+    /// # Arguments
+    ///
+    /// * `rr_type` - the numeric RR type code that this crate does not natively model
+    /// * `rdata` - the opaque RDATA octets, exactly as they appeared on the wire
+    ///
+    /// # Returns
+    ///
+    /// A new Unknown RData for use in a Resource Record
+    pub fn new(rr_type: u16, rdata: Vec<u8>) -> Unknown {
+        Unknown {
+            rr_type: rr_type,
+            rdata: rdata,
+        }
+    }
+
+    /// The numeric RR type code of the record this RDATA belongs to
+    pub fn rr_type(&self) -> u16 {
+        self.rr_type
+    }
+
+    /// The raw, opaque RDATA octets
+    pub fn rdata(&self) -> &[u8] {
+        &self.rdata
+    }
+}
+
+/// Read the RData from the given Decoder
+///
+/// The binary read path already knows the RDLENGTH from the resource record
+/// header, so this simply slurps exactly that many bytes as opaque data.
+pub fn read(decoder: &mut BinDecoder, rr_type: u16, rdata_length: u16) -> DecodeResult<Unknown> {
+    let rdata = try!(decoder.read_vec(rdata_length as usize));
+    Ok(Unknown::new(rr_type, rdata))
+}
+
+/// Emit the RData to the given Encoder
+///
+/// The octets are written back out verbatim; there is no canonicalization
+/// to perform on opaque data.
+pub fn emit(encoder: &mut BinEncoder, unknown: &Unknown) -> EncodeResult {
+    try!(encoder.emit_vec(unknown.rdata()));
+    Ok(())
+}
+
+fn is_hex_digit(c: char) -> bool {
+    c.is_ascii_hexdigit()
+}
+
+/// Parse the RData from a set of Tokens
+///
+/// Implements the RFC 3597 generic presentation format: the token `\#`
+/// followed by a decimal length, then hexadecimal digit groups (which may be
+/// split across whitespace tokens) whose decoded byte count must equal the
+/// declared length.
+pub fn parse(rr_type: u16, tokens: &Vec<Token>, _origin: Option<&Name>) -> ParseResult<Unknown> {
+    let mut token = tokens.iter();
+
+    let marker: String = try!(token.next()
+        .ok_or(ParseError::from(ParseErrorKind::MissingToken("\\#".to_string())))
+        .and_then(|t| if let &Token::CharData(ref s) = t {
+            Ok(s.clone())
+        } else {
+            Err(ParseErrorKind::UnexpectedToken(t.clone()).into())
+        }));
+
+    if marker != "\\#" {
+        return Err(ParseErrorKind::Msg(format!("expected '\\#' marker for generic RDATA, got {}",
+                                                marker))
+            .into());
+    }
+
+    let length: usize = try!(token.next()
+        .ok_or(ParseError::from(ParseErrorKind::MissingToken("length".to_string())))
+        .and_then(|t| if let &Token::CharData(ref s) = t {
+            s.parse().map_err(|_| ParseErrorKind::Msg(format!("invalid RDATA length: {}", s)).into())
+        } else {
+            Err(ParseErrorKind::UnexpectedToken(t.clone()).into())
+        }));
+
+    let mut hex = String::new();
+    for t in token {
+        if let &Token::CharData(ref s) = t {
+            if !s.chars().all(is_hex_digit) {
+                return Err(ParseErrorKind::Msg(format!("invalid hex digit group: {}", s)).into());
+            }
+            hex.push_str(s);
+        } else {
+            return Err(ParseErrorKind::UnexpectedToken(t.clone()).into());
+        }
+    }
+
+    if hex.len() % 2 != 0 {
+        return Err(ParseErrorKind::Msg("odd number of hex digits in generic RDATA".to_string())
+            .into());
+    }
+
+    let mut rdata = Vec::with_capacity(hex.len() / 2);
+    let hex_bytes = hex.as_bytes();
+    for chunk in hex_bytes.chunks(2) {
+        let byte_str = try!(str::from_utf8(chunk)
+            .map_err(|_| ParseErrorKind::Msg("invalid hex digits in generic RDATA".to_string())));
+        let byte = try!(u8::from_str_radix(byte_str, 16)
+            .map_err(|_| ParseErrorKind::Msg("invalid hex digits in generic RDATA".to_string())));
+        rdata.push(byte);
+    }
+
+    if rdata.len() != length {
+        return Err(ParseErrorKind::Msg(format!("declared RDATA length {} does not match decoded \
+                                                  byte count {}",
+                                                length,
+                                                rdata.len()))
+            .into());
+    }
+
+    Ok(Unknown::new(rr_type, rdata))
+}
+
+impl fmt::Display for Unknown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        try!(write!(f, "\\# {}", self.rdata.len()));
+        if !self.rdata.is_empty() {
+            try!(f.write_str(" "));
+            for b in &self.rdata {
+                try!(write!(f, "{:02x}", b));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+pub fn test() {
+    let rdata = Unknown::new(65535, vec![0x00, 0x01, 0x02, 0xFF]);
+
+    let mut bytes = Vec::new();
+    let mut encoder: BinEncoder = BinEncoder::new(&mut bytes);
+    assert!(emit(&mut encoder, &rdata).is_ok());
+    let bytes = encoder.as_bytes();
+
+    println!("bytes: {:?}", bytes);
+
+    let mut decoder: BinDecoder = BinDecoder::new(bytes);
+    let read_rdata = read(&mut decoder, 65535, bytes.len() as u16);
+    assert!(read_rdata.is_ok(),
+            format!("error decoding: {:?}", read_rdata.unwrap_err()));
+    assert_eq!(rdata, read_rdata.unwrap());
+}
+
+#[test]
+pub fn test_display() {
+    let rdata = Unknown::new(65535, vec![0xAB, 0xCD]);
+    assert_eq!(format!("{}", rdata), "\\# 2 abcd");
+}
+
+#[test]
+pub fn test_parse() {
+    let tokens = vec![Token::CharData("\\#".to_string()),
+                       Token::CharData("2".to_string()),
+                       Token::CharData("ABCD".to_string())];
+
+    let rdata = parse(65535, &tokens, None).unwrap();
+    assert_eq!(rdata, Unknown::new(65535, vec![0xAB, 0xCD]));
+}
+
+#[test]
+pub fn test_parse_hex_split_across_tokens() {
+    // the hexdata may be split into multiple, whitespace-separated substrings
+    let tokens = vec![Token::CharData("\\#".to_string()),
+                       Token::CharData("4".to_string()),
+                       Token::CharData("AB".to_string()),
+                       Token::CharData("CD".to_string()),
+                       Token::CharData("EF01".to_string())];
+
+    let rdata = parse(65535, &tokens, None).unwrap();
+    assert_eq!(rdata, Unknown::new(65535, vec![0xAB, 0xCD, 0xEF, 0x01]));
+}
+
+#[test]
+pub fn test_parse_missing_marker() {
+    let tokens = vec![Token::CharData("2".to_string()),
+                       Token::CharData("ABCD".to_string())];
+
+    assert!(parse(65535, &tokens, None).is_err());
+}
+
+#[test]
+pub fn test_parse_length_mismatch() {
+    // declared length of 3 but only 2 bytes of hexdata follow
+    let tokens = vec![Token::CharData("\\#".to_string()),
+                       Token::CharData("3".to_string()),
+                       Token::CharData("ABCD".to_string())];
+
+    assert!(parse(65535, &tokens, None).is_err());
+}